@@ -21,6 +21,7 @@ pub mod wasmer_traits;
 pub mod brotli;
 
 mod guest_ptr;
+mod test;
 pub mod wasip1_stub;
 
 /// Initializes a deterministic, psuedo-random number generator with a fixed seed.
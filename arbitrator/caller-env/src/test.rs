@@ -0,0 +1,200 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/OffchainLabs/nitro/blob/master/LICENSE
+
+#![cfg(test)]
+
+use crate::{wasip1_stub, ExecEnv, GuestPtr, MemAccess};
+use rand::RngCore;
+use rand_pcg::Pcg32;
+
+/// A flat, little-endian stand-in for a guest's linear memory.
+struct TestMemAccess(Vec<u8>);
+
+impl TestMemAccess {
+    fn new(size: usize) -> Self {
+        Self(vec![0; size])
+    }
+
+    fn range(&self, ptr: GuestPtr, len: usize) -> core::ops::Range<usize> {
+        let start = ptr.0 as usize;
+        let end = start + len;
+        assert!(
+            end <= self.0.len(),
+            "out of bounds access at {start}..{end}"
+        );
+        start..end
+    }
+}
+
+impl MemAccess for TestMemAccess {
+    fn read_u8(&self, ptr: GuestPtr) -> u8 {
+        u8::from_le_bytes(self.read_fixed(ptr))
+    }
+
+    fn read_u16(&self, ptr: GuestPtr) -> u16 {
+        u16::from_le_bytes(self.read_fixed(ptr))
+    }
+
+    fn read_u32(&self, ptr: GuestPtr) -> u32 {
+        u32::from_le_bytes(self.read_fixed(ptr))
+    }
+
+    fn read_u64(&self, ptr: GuestPtr) -> u64 {
+        u64::from_le_bytes(self.read_fixed(ptr))
+    }
+
+    fn write_u8(&mut self, ptr: GuestPtr, x: u8) {
+        self.write_slice(ptr, &x.to_le_bytes())
+    }
+
+    fn write_u16(&mut self, ptr: GuestPtr, x: u16) {
+        self.write_slice(ptr, &x.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, ptr: GuestPtr, x: u32) {
+        self.write_slice(ptr, &x.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, ptr: GuestPtr, x: u64) {
+        self.write_slice(ptr, &x.to_le_bytes())
+    }
+
+    fn read_slice(&self, ptr: GuestPtr, len: usize) -> Vec<u8> {
+        self.0[self.range(ptr, len)].to_vec()
+    }
+
+    fn read_fixed<const N: usize>(&self, ptr: GuestPtr) -> [u8; N] {
+        self.read_slice(ptr, N).try_into().unwrap()
+    }
+
+    fn write_slice(&mut self, ptr: GuestPtr, data: &[u8]) {
+        let range = self.range(ptr, data.len());
+        self.0[range].copy_from_slice(data);
+    }
+}
+
+/// A host environment that records everything the guest prints.
+struct TestExecEnv {
+    time: u64,
+    rng: Pcg32,
+    output: Vec<u8>,
+}
+
+impl Default for TestExecEnv {
+    fn default() -> Self {
+        Self {
+            time: 0,
+            rng: crate::create_pcg(),
+            output: vec![],
+        }
+    }
+}
+
+impl ExecEnv for TestExecEnv {
+    fn advance_time(&mut self, ns: u64) {
+        self.time += ns;
+    }
+
+    fn get_time(&self) -> u64 {
+        self.time
+    }
+
+    fn next_rand_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn print_string(&mut self, message: &[u8]) {
+        self.output.extend_from_slice(message);
+    }
+}
+
+#[test]
+fn test_mem_roundtrip() {
+    let mut mem = TestMemAccess::new(64);
+    mem.write_u64(GuestPtr(8), 0x0102_0304_0506_0708);
+    assert_eq!(mem.read_u64(GuestPtr(8)), 0x0102_0304_0506_0708);
+    assert_eq!(mem.read_u32(GuestPtr(8)), 0x0506_0708);
+    assert_eq!(mem.read_u8(GuestPtr(15)), 0x01);
+
+    mem.write_slice(GuestPtr(32), b"nitro");
+    assert_eq!(mem.read_slice(GuestPtr(32), 5), b"nitro");
+}
+
+#[test]
+fn test_args_get() {
+    let mut mem = TestMemAccess::new(64);
+    let mut env = TestExecEnv::default();
+    wasip1_stub::args_get(&mut mem, &mut env, GuestPtr(0), GuestPtr(16));
+    assert_eq!(mem.read_u32(GuestPtr(0)), 16);
+    assert_eq!(mem.read_slice(GuestPtr(16), 4), b"bin\0");
+}
+
+#[test]
+fn test_fd_write_iovecs() {
+    let mut mem = TestMemAccess::new(128);
+    let mut env = TestExecEnv::default();
+
+    // two 8-byte iovecs of (ptr, len)
+    mem.write_slice(GuestPtr(64), b"hello ");
+    mem.write_slice(GuestPtr(96), b"world");
+    mem.write_u32(GuestPtr(0), 64);
+    mem.write_u32(GuestPtr(4), 6);
+    mem.write_u32(GuestPtr(8), 96);
+    mem.write_u32(GuestPtr(12), 5);
+
+    let errno = wasip1_stub::fd_write(&mut mem, &mut env, 1, GuestPtr(0), 2, GuestPtr(32));
+    assert_eq!(errno.0, wasip1_stub::ERRNO_SUCCESS.0);
+    assert_eq!(mem.read_u32(GuestPtr(32)), 11);
+    assert_eq!(env.output, b"hello world");
+
+    let errno = wasip1_stub::fd_write(&mut mem, &mut env, 3, GuestPtr(0), 2, GuestPtr(32));
+    assert_eq!(errno.0, wasip1_stub::ERRNO_BADF.0);
+}
+
+#[test]
+fn test_poll_oneoff_layout() {
+    let mut mem = TestMemAccess::new(256);
+    let mut env = TestExecEnv::default();
+
+    // a non-clock subscription followed by a clock one, each 48 bytes
+    mem.write_u32(GuestPtr(0), 7);
+    mem.write_u32(GuestPtr(8), 1);
+    mem.write_u32(GuestPtr(48), 9);
+    mem.write_u32(GuestPtr(56), 0);
+
+    let errno = wasip1_stub::poll_oneoff(
+        &mut mem,
+        &mut env,
+        GuestPtr(0),
+        GuestPtr(128),
+        2,
+        GuestPtr(192),
+    );
+    assert_eq!(errno.0, wasip1_stub::ERRNO_SUCCESS.0);
+    assert_eq!(mem.read_u32(GuestPtr(128)), 9);
+    assert_eq!(mem.read_u32(GuestPtr(136)), 0);
+    assert_eq!(mem.read_u32(GuestPtr(192)), 1);
+    assert_eq!(env.time, 10_000_000);
+}
+
+#[test]
+fn test_clock_and_random() {
+    let mut mem = TestMemAccess::new(64);
+    let mut env = TestExecEnv::default();
+
+    wasip1_stub::clock_time_get(&mut mem, &mut env, 0, 0, GuestPtr(0));
+    wasip1_stub::clock_time_get(&mut mem, &mut env, 1, 0, GuestPtr(8));
+    assert_eq!(mem.read_u64(GuestPtr(0)), 10_000_000);
+    assert_eq!(mem.read_u64(GuestPtr(8)), 20_000_000);
+
+    // a partial word must not write past the requested length
+    mem.write_u8(GuestPtr(38), 0xff);
+    wasip1_stub::random_get(&mut mem, &mut env, GuestPtr(32), 6);
+    assert_eq!(mem.read_u8(GuestPtr(38)), 0xff);
+
+    let mut expected = TestExecEnv::default();
+    let first = expected.next_rand_u32().to_le_bytes();
+    let second = expected.next_rand_u32().to_le_bytes();
+    assert_eq!(mem.read_slice(GuestPtr(32), 4), first);
+    assert_eq!(mem.read_slice(GuestPtr(36), 2), second[..2]);
+}